@@ -1,16 +1,19 @@
-//! A writeable buffer that implements [`fmt::Write`] or [`ufmt::uWrite`](https://docs.rs/ufmt/latest/ufmt/trait.uWrite.html).
+//! A writeable buffer that implements [`fmt::Write`] and, with the `ufmt` feature, [`ufmt::uWrite`](https://docs.rs/ufmt/latest/ufmt/trait.uWrite.html).
 //!
 //! # Example
 //! ```
 //! use writebuf_core::WriteBuf;
-//! use ufmt::{uwrite, uWrite};
+//! use ufmt::uwrite;
 //!
-//! // write to buffer
+//! // write to buffer via ufmt ...
 //! let mut buf: WriteBuf<10> = WriteBuf::from("123");
 //! uwrite!(&mut buf, "{}", "456").ok();
 //! uwrite!(&mut buf, "{}", 789).ok();
-//! buf.write_str("0").ok();
-//! buf.write_str("E").err();
+//!
+//! // ... or via core::fmt, side by side
+//! core::fmt::Write::write_str(&mut buf, "0").ok();
+//! core::fmt::Write::write_str(&mut buf, "E").ok();
+//! assert!(buf.truncated());
 //!
 //! // convert to ASCII string
 //! buf.into_ascii_lossy().as_str();
@@ -18,12 +21,19 @@
 //!
 //! # ufmt
 //! ufmt is more compact than core::fmt. By default, ufmt feature is enabled.
+//! `core::fmt::Write` is always implemented, so both can be used together in
+//! a crate graph where one dependency formats via `core::fmt` and another
+//! via `ufmt`.
+//!
+//! # StrBuf
+//! [`StrBuf`] is a sibling type for text: it maintains the invariant that
+//! its contents are always valid UTF-8, with the same truncation and
+//! buffer-reuse API as `WriteBuf`.
 
 #![cfg_attr(not(test), no_std)]
 
 use heapless::{Vec, String};
 
-#[cfg(not(feature = "ufmt"))]
 use core::fmt;
 use core::ops::{Deref, DerefMut};
 
@@ -33,6 +43,8 @@ use ufmt::uWrite;
 #[derive(Default, Clone, Debug)]
 pub struct WriteBuf<const N: usize> {
     buffer: Vec<u8, N>,
+    overflowed: bool,
+    dropped: usize,
 }
 
 impl<const N: usize> WriteBuf<N> {
@@ -40,6 +52,59 @@ impl<const N: usize> WriteBuf<N> {
         Self::default()
     }
 
+    /// Total capacity of the buffer, in bytes
+    pub const fn capacity() -> usize {
+        N
+    }
+
+    /// Clear the buffer for reuse, keeping the existing storage
+    pub fn reset(&mut self) {
+        self.buffer.clear();
+        self.reset_truncation();
+    }
+
+    /// Free space left in the buffer, in bytes
+    pub fn remaining(&self) -> usize {
+        N - self.buffer.len()
+    }
+
+    /// Whether the buffer has no free space left
+    pub fn is_full(&self) -> bool {
+        self.buffer.len() == N
+    }
+
+    /// Whether any write since the last [`Self::reset_truncation`] didn't fully fit
+    pub fn truncated(&self) -> bool {
+        self.overflowed
+    }
+
+    /// Number of bytes dropped by writes that didn't fully fit, since the last [`Self::reset_truncation`]
+    pub fn dropped_bytes(&self) -> usize {
+        self.dropped
+    }
+
+    /// Clear the truncation flag and dropped byte counter
+    pub fn reset_truncation(&mut self) {
+        self.overflowed = false;
+        self.dropped = 0;
+    }
+
+    /// Copy as much of `s` as fits on a `char` boundary, marking truncation if it doesn't all fit
+    fn push_str(&mut self, s: &str) {
+        let room = N - self.buffer.len();
+        if s.len() <= room {
+            self.buffer.extend_from_slice(s.as_bytes()).ok();
+        } else {
+            let mut cut = room;
+            while cut > 0 && !s.is_char_boundary(cut) {
+                cut -= 1;
+            }
+            self.buffer.extend_from_slice(s[..cut].as_bytes()).ok();
+            self.overflowed = true;
+            self.dropped += s.len() - cut;
+        }
+    }
+
     /// Try convert to UTF-8 str
     pub fn to_str(&self) -> Result<&str, ()> {
         core::str::from_utf8(self.buffer.as_slice()).map_err(|_e| ())
@@ -57,6 +122,151 @@ impl<const N: usize> WriteBuf<N> {
         }
         s
     }
+
+    /// Borrowing version of [`Self::into_utf8_lossy`]
+    pub fn to_utf8_lossy(&self) -> String<N> {
+        let mut s = String::<N>::new();
+        push_utf8_lossy(self.buffer.as_slice(), &mut s);
+        s
+    }
+
+    /// Decode the buffer as UTF-8, replacing invalid sequences with `U+FFFD`
+    ///
+    /// Unlike [`Self::into_ascii_lossy`], valid multi-byte UTF-8 passes
+    /// through unchanged. The replacement char is 3 bytes, so the result can
+    /// need more room than the input; a push that would overflow capacity
+    /// stops the conversion early and returns what fits.
+    pub fn into_utf8_lossy(self) -> String<N> {
+        self.to_utf8_lossy()
+    }
+
+    /// Render the buffer as printable ASCII, escaping anything that isn't
+    ///
+    /// Bytes `0x20..=0x7E` pass through unchanged, except `\\` and `"` which
+    /// are backslash-escaped; `\n`, `\r` and `\t` use their short escapes;
+    /// everything else becomes `\xNN` with lowercase hex. Unlike
+    /// [`Self::into_ascii_lossy`], the result is unambiguous and can be
+    /// parsed back with [`Self::from_escaped`].
+    pub fn write_escaped<const M: usize>(&self, out: &mut String<M>) {
+        for &c in self.iter() {
+            if push_escaped_byte(c, out).is_err() {
+                return;
+            }
+        }
+    }
+
+    /// Owning version of [`Self::write_escaped`]
+    pub fn into_escaped<const M: usize>(self) -> String<M> {
+        let mut s = String::<M>::new();
+        self.write_escaped(&mut s);
+        s
+    }
+
+    /// Parse the output of [`Self::write_escaped`] back into a buffer
+    pub fn from_escaped(s: &str) -> Result<Self, EscapeError> {
+        let mut buf = Self::new();
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            let byte = if c == '\\' {
+                match chars.next().ok_or(EscapeError::UnexpectedEnd)? {
+                    '\\' => b'\\',
+                    '"' => b'"',
+                    'n' => b'\n',
+                    'r' => b'\r',
+                    't' => b'\t',
+                    'x' => {
+                        let hi = chars.next().ok_or(EscapeError::UnexpectedEnd)?;
+                        let lo = chars.next().ok_or(EscapeError::UnexpectedEnd)?;
+                        let hi = hi.to_digit(16).ok_or(EscapeError::InvalidEscape)?;
+                        let lo = lo.to_digit(16).ok_or(EscapeError::InvalidEscape)?;
+                        ((hi << 4) | lo) as u8
+                    }
+                    _ => return Err(EscapeError::InvalidEscape),
+                }
+            } else if c.is_ascii() && (0x20..=0x7E).contains(&(c as u8)) {
+                c as u8
+            } else {
+                return Err(EscapeError::InvalidEscape);
+            };
+            buf.buffer.push(byte).map_err(|_| EscapeError::Overflow)?;
+        }
+        Ok(buf)
+    }
+}
+
+/// Error parsing an escaped string produced by [`WriteBuf::write_escaped`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EscapeError {
+    /// An escape sequence was cut off at the end of the input
+    UnexpectedEnd,
+    /// An escape sequence was not recognized, or a character outside
+    /// printable ASCII appeared unescaped
+    InvalidEscape,
+    /// The decoded bytes did not fit in the destination buffer's capacity
+    Overflow,
+}
+
+fn push_escaped_byte<const M: usize>(byte: u8, out: &mut String<M>) -> Result<(), ()> {
+    match byte {
+        b'\\' => out.push_str("\\\\").map_err(|_| ()),
+        b'"' => out.push_str("\\\"").map_err(|_| ()),
+        b'\n' => out.push_str("\\n").map_err(|_| ()),
+        b'\r' => out.push_str("\\r").map_err(|_| ()),
+        b'\t' => out.push_str("\\t").map_err(|_| ()),
+        0x20..=0x7E => out.push(byte as char).map_err(|_| ()),
+        _ => {
+            let hex = [b'\\', b'x', hex_digit(byte >> 4), hex_digit(byte & 0xF)];
+            // SAFETY: all four bytes above are ASCII.
+            out.push_str(unsafe { core::str::from_utf8_unchecked(&hex) }).map_err(|_| ())
+        }
+    }
+}
+
+fn hex_digit(nibble: u8) -> u8 {
+    match nibble {
+        0..=9 => b'0' + nibble,
+        _ => b'a' + (nibble - 10),
+    }
+}
+
+/// Replacement character emitted for invalid UTF-8 sequences
+const REPLACEMENT_CHAR: char = '\u{FFFD}';
+
+fn push_utf8_lossy<const N: usize>(mut bytes: &[u8], out: &mut String<N>) {
+    while !bytes.is_empty() {
+        match core::str::from_utf8(bytes) {
+            Ok(valid) => {
+                if out.push_str(valid).is_err() {
+                    // Push as much of the valid run as fits, then stop.
+                    for c in valid.chars() {
+                        if out.push(c).is_err() {
+                            return;
+                        }
+                    }
+                }
+                return;
+            }
+            Err(e) => {
+                let valid_len = e.valid_up_to();
+                if valid_len > 0 {
+                    // SAFETY: `valid_up_to` guarantees this prefix is valid UTF-8.
+                    let valid = unsafe { core::str::from_utf8_unchecked(&bytes[..valid_len]) };
+                    for c in valid.chars() {
+                        if out.push(c).is_err() {
+                            return;
+                        }
+                    }
+                }
+                if out.push(REPLACEMENT_CHAR).is_err() {
+                    return;
+                }
+                // Skip past the maximal invalid subpart and keep scanning; a
+                // following invalid subpart gets its own replacement char.
+                let invalid_len = e.error_len().unwrap_or(bytes.len() - valid_len);
+                bytes = &bytes[valid_len + invalid_len..];
+            }
+        }
+    }
 }
 
 impl<T: AsRef<[u8]>, const N: usize> From<T> for WriteBuf<N> {
@@ -82,10 +292,10 @@ impl<const N: usize> DerefMut for WriteBuf<N> {
     }
 }
 
-#[cfg(not(feature = "ufmt"))]
 impl<const N: usize> fmt::Write for WriteBuf<N> {
     fn write_str(&mut self, s: &str) -> fmt::Result {
-        self.buffer.write_str(s)
+        self.push_str(s);
+        Ok(())
     }
 }
 
@@ -94,7 +304,108 @@ impl<const N: usize> uWrite for WriteBuf<N> {
     type Error = ();
 
     fn write_str(&mut self, s: &str) -> Result<(), Self::Error> {
-        self.buffer.write_str(s)
+        self.push_str(s);
+        Ok(())
+    }
+}
+
+/// A fixed-capacity string buffer that always holds valid UTF-8
+///
+/// Unlike [`WriteBuf`], which stores raw bytes and only validates UTF-8 on
+/// demand via [`WriteBuf::to_str`], `StrBuf` maintains the invariant that its
+/// contents are always valid UTF-8, so [`Self::as_str`] is infallible.
+/// Pushes that don't fully fit are truncated on a `char` boundary rather
+/// than splitting one, with the same observable-truncation and buffer-reuse
+/// API as [`WriteBuf`].
+#[derive(Default, Clone, Debug)]
+pub struct StrBuf<const N: usize> {
+    buffer: String<N>,
+    overflowed: bool,
+    dropped: usize,
+}
+
+impl<const N: usize> StrBuf<N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Total capacity of the buffer, in bytes
+    pub const fn capacity() -> usize {
+        N
+    }
+
+    /// Clear the buffer for reuse, keeping the existing storage
+    pub fn reset(&mut self) {
+        self.buffer.clear();
+        self.reset_truncation();
+    }
+
+    /// Free space left in the buffer, in bytes
+    pub fn remaining(&self) -> usize {
+        N - self.buffer.len()
+    }
+
+    /// Whether the buffer has no free space left
+    pub fn is_full(&self) -> bool {
+        self.buffer.len() == N
+    }
+
+    /// Whether any push since the last [`Self::reset_truncation`] didn't fully fit
+    pub fn truncated(&self) -> bool {
+        self.overflowed
+    }
+
+    /// Number of bytes dropped by pushes that didn't fully fit, since the last [`Self::reset_truncation`]
+    pub fn dropped_bytes(&self) -> usize {
+        self.dropped
+    }
+
+    /// Clear the truncation flag and dropped byte counter
+    pub fn reset_truncation(&mut self) {
+        self.overflowed = false;
+        self.dropped = 0;
+    }
+
+    /// Append a single `char`, only if the whole encoding fits
+    pub fn push(&mut self, c: char) -> Result<(), ()> {
+        let mut encode_buf = [0u8; 4];
+        let encoded = c.encode_utf8(&mut encode_buf);
+        if encoded.len() > self.remaining() {
+            self.overflowed = true;
+            self.dropped += encoded.len();
+            return Err(());
+        }
+        self.buffer.push_str(encoded).ok();
+        Ok(())
+    }
+
+    /// Append as much of `s` as fits on a `char` boundary, marking truncation if it doesn't all fit
+    pub fn push_str(&mut self, s: &str) {
+        let room = self.remaining();
+        if s.len() <= room {
+            self.buffer.push_str(s).ok();
+        } else {
+            let mut cut = room;
+            while cut > 0 && !s.is_char_boundary(cut) {
+                cut -= 1;
+            }
+            self.buffer.push_str(&s[..cut]).ok();
+            self.overflowed = true;
+            self.dropped += s.len() - cut;
+        }
+    }
+
+    /// Borrow the buffer's contents as a `&str`
+    pub fn as_str(&self) -> &str {
+        &self.buffer
+    }
+}
+
+impl<const N: usize> Deref for StrBuf<N> {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.buffer
     }
 }
 
@@ -111,8 +422,24 @@ mod tests {
     #[test]
     fn test_full() {
         let mut buf = WriteBuf::<10>::from("123456789");
-        buf.write_str("abc").err();
-        assert_eq!(buf.to_str().unwrap(), "123456789");
+        buf.write_str("abc").ok();
+        assert_eq!(buf.to_str().unwrap(), "123456789a");
+        assert!(buf.truncated());
+        assert_eq!(buf.dropped_bytes(), 2);
+        buf.reset_truncation();
+        assert!(!buf.truncated());
+        assert_eq!(buf.dropped_bytes(), 0);
+    }
+
+    #[test]
+    fn test_full_mid_char() {
+        // "é" is 2 bytes; only 1 byte of room is left, so the whole char is
+        // dropped rather than splitting it and leaving invalid UTF-8 behind.
+        let mut buf = WriteBuf::<3>::from("ab");
+        buf.write_str("é").ok();
+        assert_eq!(buf.to_str().unwrap(), "ab");
+        assert!(buf.truncated());
+        assert_eq!(buf.dropped_bytes(), 2);
     }
 
     #[test]
@@ -122,4 +449,107 @@ mod tests {
         buf[9] = 0x80u8;
         assert_eq!(buf.into_ascii_lossy(), "123456789~");
     }
+
+    #[test]
+    fn test_into_utf8_lossy_valid() {
+        let buf = WriteBuf::<20>::from("héllo wörld");
+        assert_eq!(buf.into_utf8_lossy(), "héllo wörld");
+    }
+
+    #[test]
+    fn test_into_utf8_lossy_invalid() {
+        let mut buf = WriteBuf::<10>::new();
+        buf.extend_from_slice(b"ab\xFFcd").ok();
+        assert_eq!(buf.into_utf8_lossy(), "ab\u{FFFD}cd");
+    }
+
+    #[test]
+    fn test_into_escaped() {
+        let mut buf = WriteBuf::<10>::new();
+        buf.extend_from_slice(b"a\"\\\n\x01").ok();
+        let escaped: String<20> = buf.into_escaped();
+        assert_eq!(escaped, "a\\\"\\\\\\n\\x01");
+    }
+
+    #[test]
+    fn test_escaped_round_trip() {
+        let mut buf = WriteBuf::<10>::new();
+        buf.extend_from_slice(b"a\"\\\n\x01").ok();
+        let escaped: String<20> = buf.clone().into_escaped();
+        let decoded = WriteBuf::<10>::from_escaped(&escaped).unwrap();
+        assert_eq!(decoded.to_str().unwrap(), buf.to_str().unwrap());
+    }
+
+    #[test]
+    fn test_from_escaped_invalid() {
+        assert_eq!(WriteBuf::<10>::from_escaped("\\q").unwrap_err(), EscapeError::InvalidEscape);
+        assert_eq!(WriteBuf::<10>::from_escaped("\\").unwrap_err(), EscapeError::UnexpectedEnd);
+    }
+
+    #[test]
+    fn test_reset_and_capacity() {
+        let mut buf = WriteBuf::<10>::from("12345");
+        assert_eq!(WriteBuf::<10>::capacity(), 10);
+        assert_eq!(buf.remaining(), 5);
+        assert!(!buf.is_full());
+
+        buf.write_str("67890").ok();
+        assert_eq!(buf.remaining(), 0);
+        assert!(buf.is_full());
+
+        buf.reset();
+        assert_eq!(buf.remaining(), 10);
+        assert!(!buf.is_full());
+        assert_eq!(buf.to_str().unwrap(), "");
+    }
+
+    #[test]
+    fn test_str_buf_push() {
+        let mut buf = StrBuf::<5>::new();
+        buf.push('h').ok();
+        buf.push('é').ok();
+        assert_eq!(buf.as_str(), "hé");
+        assert_eq!(&*buf, "hé");
+    }
+
+    #[test]
+    fn test_str_buf_push_overflow() {
+        let mut buf = StrBuf::<3>::new();
+        buf.push_str("ab");
+        // 'é' is 2 bytes and would overflow the 3-byte capacity; rejected whole.
+        assert!(buf.push('é').is_err());
+        assert_eq!(buf.as_str(), "ab");
+        assert!(buf.truncated());
+        assert_eq!(buf.dropped_bytes(), 2);
+    }
+
+    #[test]
+    fn test_str_buf_push_str_truncates_on_char_boundary() {
+        let mut buf = StrBuf::<3>::new();
+        buf.push_str("ab");
+        // "é" is 2 bytes but only 1 byte of room is left; the whole char is
+        // dropped rather than splitting it.
+        buf.push_str("é");
+        assert_eq!(buf.as_str(), "ab");
+        assert!(buf.truncated());
+        assert_eq!(buf.dropped_bytes(), 2);
+    }
+
+    #[test]
+    fn test_str_buf_reset_and_capacity() {
+        let mut buf = StrBuf::<5>::new();
+        assert_eq!(StrBuf::<5>::capacity(), 5);
+        buf.push_str("ab");
+        assert_eq!(buf.remaining(), 3);
+        assert!(!buf.is_full());
+
+        buf.push_str("cde");
+        assert_eq!(buf.remaining(), 0);
+        assert!(buf.is_full());
+
+        buf.reset();
+        assert_eq!(buf.remaining(), 5);
+        assert!(!buf.is_full());
+        assert_eq!(buf.as_str(), "");
+    }
 }
\ No newline at end of file